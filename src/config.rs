@@ -10,6 +10,14 @@ pub struct Config {
     pub fuzzy: bool,
     pub show_remote_branches: bool,
     pub limit: Option<usize>,
+    pub format: String,
+    pub protected_branches: Vec<String>,
+    pub truncation_length: Option<usize>,
+    pub truncation_symbol: String,
+    pub ignore_branches: Vec<String>,
+    pub actions: bool,
+    pub only_attached: bool,
+    pub protected_symbol: String,
 }
 
 impl Default for Config {
@@ -19,6 +27,17 @@ impl Default for Config {
             fuzzy: true,
             show_remote_branches: false,
             limit: Some(20usize),
+            format: "$protected$branch  $time  $author".to_string(),
+            protected_branches: ["main", "master", "dev", "stable"]
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            truncation_length: None,
+            truncation_symbol: "…".to_string(),
+            ignore_branches: Vec::new(),
+            actions: false,
+            only_attached: false,
+            protected_symbol: "🔒 ".to_string(),
         }
     }
 }
@@ -40,8 +59,30 @@ macro_rules! extract_config_value {
     };
 }
 
+/// A source of environment-variable overrides, injectable so `Config::resolve` stays testable
+/// without touching the real process environment.
+pub trait EnvSource {
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+/// The real process environment, used outside of tests.
+pub struct SystemEnv;
+
+impl EnvSource for SystemEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+}
+
 impl Config {
     pub fn from_git_config(git_config: &git2::Config) -> anyhow::Result<Config> {
+        Config::resolve(git_config, &SystemEnv)
+    }
+
+    /// Resolves the effective configuration by layering, in increasing priority:
+    /// defaults -> git config -> environment variables. Environment variables let users flip
+    /// behavior for a single invocation without editing `.gitconfig`.
+    pub fn resolve(git_config: &git2::Config, env: &dyn EnvSource) -> anyhow::Result<Config> {
         let mut config = Config::default();
         if let Some(value) = extract_config_value!(git_config, bool, "select-branch.fuzzy") {
             config.fuzzy = value;
@@ -58,29 +99,159 @@ impl Config {
                 .with_context(|| "Could not parse theme configuration")?;
         }
 
-        if let Some("none") = extract_config_value!(git_config, str, "select-branch.limit") {
-            config.limit = None
-        } else if let Some(limit) = extract_config_value!(git_config, i64, "select-branch.limit") {
-            if limit <= 0 {
+        if let Some(value) = extract_config_value!(git_config, str, "select-branch.format") {
+            config.format = value.to_string();
+        }
+
+        if let Some(value) = extract_config_value!(git_config, bool, "select-branch.actions") {
+            config.actions = value;
+        }
+
+        if let Some(value) = extract_config_value!(git_config, bool, "select-branch.only-attached")
+        {
+            config.only_attached = value;
+        }
+
+        if let Some(value) = extract_multivar_list(git_config, "select-branch.protected-branches")?
+        {
+            config.protected_branches = value;
+        }
+
+        if let Some(value) = extract_multivar_list(git_config, "select-branch.ignore-branches")? {
+            config.ignore_branches = value;
+        }
+
+        if let Some(value) =
+            extract_config_value!(git_config, str, "select-branch.protected-symbol")
+        {
+            config.protected_symbol = value.to_string();
+        }
+
+        if let Some(value) =
+            extract_config_value!(git_config, str, "select-branch.truncation-symbol")
+        {
+            config.truncation_symbol = value.to_string();
+        }
+
+        if let Some(length) =
+            extract_config_value!(git_config, i64, "select-branch.truncation-length")
+        {
+            if length <= 0 {
                 return Err(anyhow!(
-                    "\"{}\" is not a valid \"select-branch.limit\" value.\n\
-                    The value must be either a positive integer, or \"none\". e.g.:\n\
-                    > git config --global select-branch.limit none\n\
-                    or\n\
-                    > git config --global select-branch.limit 20",
-                    limit
+                    "\"{}\" is not a valid \"select-branch.truncation-length\" value.\n\
+                    The value must be a positive integer.",
+                    length
                 ));
             }
-            config.limit = Some(
-                usize::try_from(limit)
-                    .with_context(|| format!("Can't convert {limit:?} to usize"))?,
+            config.truncation_length = Some(
+                usize::try_from(length)
+                    .with_context(|| format!("Can't convert {length:?} to usize"))?,
             )
         }
 
+        if let Some(value) = extract_config_value!(git_config, str, "select-branch.limit") {
+            config.limit = parse_limit(value)?;
+        }
+
+        if let Some(value) = env.get("GIT_SELECT_BRANCH_FUZZY") {
+            config.fuzzy = parse_bool_env("GIT_SELECT_BRANCH_FUZZY", &value)?;
+        }
+
+        if let Some(value) = env.get("GIT_SELECT_BRANCH_SHOW_REMOTE") {
+            config.show_remote_branches = parse_bool_env("GIT_SELECT_BRANCH_SHOW_REMOTE", &value)?;
+        }
+
+        if let Some(value) = env.get("GIT_SELECT_BRANCH_THEME") {
+            config.theme = crate::match_theme_config(&value)
+                .with_context(|| "Could not parse theme configuration")?;
+        }
+
+        if let Some(value) = env.get("GIT_SELECT_BRANCH_LIMIT") {
+            config.limit = parse_limit(&value)?;
+        }
+
         Ok(config)
     }
 }
 
+/// Parses a `select-branch.limit` value, shared by the git-config and environment-variable
+/// sources so both accept the same two shapes: a positive integer, or the literal `"none"`.
+fn parse_limit(value: &str) -> anyhow::Result<Option<usize>> {
+    if value == "none" {
+        return Ok(None);
+    }
+
+    let limit: i64 = value.parse().with_context(|| {
+        format!(
+            "\"{value}\" is not a valid \"select-branch.limit\" value.\n\
+            The value must be either a positive integer, or \"none\". e.g.:\n\
+            > git config --global select-branch.limit none\n\
+            or\n\
+            > git config --global select-branch.limit 20"
+        )
+    })?;
+
+    if limit <= 0 {
+        return Err(anyhow!(
+            "\"{}\" is not a valid \"select-branch.limit\" value.\n\
+            The value must be either a positive integer, or \"none\". e.g.:\n\
+            > git config --global select-branch.limit none\n\
+            or\n\
+            > git config --global select-branch.limit 20",
+            limit
+        ));
+    }
+
+    Ok(Some(usize::try_from(limit).with_context(|| {
+        format!("Can't convert {limit:?} to usize")
+    })?))
+}
+
+/// Parses a boolean-valued environment variable override, e.g. `GIT_SELECT_BRANCH_FUZZY`.
+fn parse_bool_env(name: &str, value: &str) -> anyhow::Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(anyhow!(
+            "\"{value}\" is not a valid value for {name}, expected a boolean"
+        )),
+    }
+}
+
+/// Reads a branch-name list config value, e.g. `select-branch.protected-branches` or
+/// `select-branch.ignore-branches`. These can be set either as a single whitespace- or
+/// comma-separated string, or as a git config multivar (multiple `--add`ed entries); both forms
+/// are flattened into a single list of branch names.
+fn extract_multivar_list(
+    git_config: &git2::Config,
+    option_name: &str,
+) -> anyhow::Result<Option<Vec<String>>> {
+    let mut entries = match git_config.multivar(option_name, None) {
+        Ok(entries) => entries,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err).with_context(|| format!("Error reading {option_name}")),
+    };
+
+    let mut values = Vec::new();
+    while let Some(entry) = entries.next() {
+        let entry = entry.with_context(|| format!("Error reading {option_name}"))?;
+        if let Some(value) = entry.value() {
+            values.extend(
+                value
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|s| !s.is_empty())
+                    .map(ToString::to_string),
+            );
+        }
+    }
+
+    if values.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(values))
+    }
+}
+
 fn map_git2_not_found_to_none<E>(
     config_result: anyhow::Result<E, git2::Error>,
 ) -> Result<Option<E>, git2::Error> {
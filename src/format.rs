@@ -0,0 +1,138 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use git2::Time;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::config::Config;
+use crate::BranchInfo;
+
+/// Renders a `select-branch.format` template for a single branch row.
+///
+/// Templates are plain strings containing `$name` tokens, in the same spirit as Starship's
+/// `git_branch` module: each recognized token is substituted with the matching piece of commit
+/// metadata, falling back to an empty string when the data isn't available. Anything else in the
+/// template (literal text, whitespace) passes through untouched.
+///
+/// This is a single pass over the template: a token is only ever recognized in the original
+/// `config.format` string, never in a value that was itself just substituted in (e.g. a commit
+/// message that happens to contain the literal text `$time`).
+pub fn format_branch_row(config: &Config, branch_info: &BranchInfo) -> String {
+    let mut output = String::with_capacity(config.format.len());
+    let mut rest = config.format.as_str();
+
+    while let Some(dollar) = rest.find('$') {
+        output.push_str(&rest[..dollar]);
+        rest = &rest[dollar..];
+
+        match token_value(config, branch_info, rest) {
+            Some((value, consumed)) => {
+                output.push_str(&value);
+                rest = &rest[consumed..];
+            }
+            None => {
+                output.push('$');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+
+    output
+}
+
+/// Matches a `$name` token at the start of `input`, returning its substituted value and the
+/// number of bytes of `input` it consumed. Returns `None` if `input` doesn't start with a
+/// recognized token, in which case the caller passes the `$` through literally.
+///
+/// A token only matches if it's immediately followed by a non-identifier character or the end of
+/// the string — otherwise e.g. `$authored_by` would match `$author` and render as
+/// `<author-name>ed_by`.
+fn token_value(config: &Config, branch_info: &BranchInfo, input: &str) -> Option<(String, usize)> {
+    const TOKENS: &[(&str, fn(&Config, &BranchInfo) -> String)] = &[
+        ("$branch", |config, branch_info| {
+            truncate(
+                &branch_info.shorthand,
+                config.truncation_length,
+                &config.truncation_symbol,
+            )
+        }),
+        ("$author", |_config, branch_info| {
+            branch_info.commit_author_name.clone().unwrap_or_default()
+        }),
+        ("$message", |_config, branch_info| {
+            branch_info.commit_message.clone().unwrap_or_default()
+        }),
+        ("$time", |_config, branch_info| {
+            format_relative_time(branch_info.commit_time)
+        }),
+        ("$ahead_behind", |_config, branch_info| {
+            format_ahead_behind(branch_info.ahead_behind)
+        }),
+        ("$protected", |config, branch_info| {
+            match branch_info.is_protected {
+                true => config.protected_symbol.clone(),
+                false => String::new(),
+            }
+        }),
+    ];
+
+    TOKENS
+        .iter()
+        .find(|(token, _)| {
+            input.starts_with(token)
+                && !input[token.len()..]
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        })
+        .map(|(token, render)| (render(config, branch_info), token.len()))
+}
+
+/// Formats an ahead/behind pair as e.g. `"⇡2⇣1"`, in the same style as Starship's `git_status`.
+/// Branches with no upstream, and branches that are fully in sync with their upstream, render
+/// as an empty string.
+fn format_ahead_behind(ahead_behind: Option<(usize, usize)>) -> String {
+    match ahead_behind {
+        Some((0, 0)) | None => String::new(),
+        Some((ahead, 0)) => format!("⇡{ahead}"),
+        Some((0, behind)) => format!("⇣{behind}"),
+        Some((ahead, behind)) => format!("⇡{ahead}⇣{behind}"),
+    }
+}
+
+/// Truncates `value` to at most `length` graphemes, appending `symbol` when it was shortened.
+/// Truncation happens on Unicode grapheme-cluster boundaries so multi-byte branch names (emoji,
+/// combining characters, ...) aren't split mid-character.
+fn truncate(value: &str, length: Option<usize>, symbol: &str) -> String {
+    match length {
+        Some(length) => {
+            let graphemes = value.graphemes(true).collect::<Vec<_>>();
+            if graphemes.len() <= length {
+                value.to_string()
+            } else {
+                format!("{}{}", graphemes[..length].concat(), symbol)
+            }
+        }
+        None => value.to_string(),
+    }
+}
+
+/// Formats a commit time as a short human-relative string, e.g. `"3 hours ago"`.
+fn format_relative_time(time: Time) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let seconds = (now - time.seconds()).max(0);
+
+    let (amount, unit) = match seconds {
+        s if s < 60 => (s, "second"),
+        s if s < 60 * 60 => (s / 60, "minute"),
+        s if s < 60 * 60 * 24 => (s / (60 * 60), "hour"),
+        s if s < 60 * 60 * 24 * 30 => (s / (60 * 60 * 24), "day"),
+        s if s < 60 * 60 * 24 * 365 => (s / (60 * 60 * 24 * 30), "month"),
+        s => (s / (60 * 60 * 24 * 365), "year"),
+    };
+
+    format!("{amount} {unit}{} ago", if amount == 1 { "" } else { "s" })
+}
@@ -9,9 +9,9 @@ use std::{env, process};
 
 use anyhow::{anyhow, Context, Result};
 use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
-use dialoguer::{FuzzySelect, Select};
+use dialoguer::{Confirm, FuzzySelect, Input, Select};
 use expect_exit::Expected;
-use git2::{BranchType, Commit, Reference, Repository, Signature, Time};
+use git2::{Branch, BranchType, Commit, Reference, Repository, Signature, Time};
 use thiserror::Error;
 
 use config::Config;
@@ -51,8 +51,16 @@ fn run_tui() -> Result<()> {
         .with_context(|| "Error reading configuration from git")?;
 
     let current_branch = get_current_branch(&repo)?;
+    let show_detached = config.only_attached
+        && repo
+            .head_detached()
+            .with_context(|| "Could not determine whether HEAD is detached")?;
     let sorted_choices = get_sorted_choices(&config, &repo)?;
-    let options = get_branch_options(sorted_choices.clone(), current_branch.as_deref());
+    let options = get_branch_options(
+        sorted_choices.clone(),
+        current_branch.as_deref(),
+        show_detached,
+    );
 
     ctrlc::set_handler(move || {
         dialoguer_reset_cursor_hack();
@@ -79,7 +87,12 @@ fn run_tui() -> Result<()> {
                 let selected_branch = &options[selection];
                 match selected_branch {
                     Choice::Default(_) => Err(SelectBranchError::Aborted.into()),
-                    Choice::Branch(branch_info) => checkout(repo, branch_info),
+                    Choice::Branch(branch_info) => match config.actions {
+                        true => {
+                            perform_action(&config, repo, branch_info, current_branch.as_deref())
+                        }
+                        false => checkout(repo, branch_info),
+                    },
                 }
             }
             None => Err(SelectBranchError::Aborted.into()),
@@ -123,8 +136,17 @@ struct BranchInfo {
     pub shorthand: String,
     pub branch_type: BranchType,
     pub commit_time: Time,
+    /// The commit's summary line (i.e. its first line), not the full, possibly multi-line,
+    /// commit message — a picker row can only ever show one line.
     pub commit_message: Option<String>,
     pub commit_author_name: Option<String>,
+    /// The row text to show in the picker, rendered from `select-branch.format`.
+    pub label: String,
+    /// Whether this branch is listed in `select-branch.protected-branches`.
+    pub is_protected: bool,
+    /// Commits ahead/behind its upstream, as `(ahead, behind)`. `None` for branches with no
+    /// upstream (and for remote branches, which don't have one).
+    pub ahead_behind: Option<(usize, usize)>,
 }
 
 #[derive(Debug, Clone)]
@@ -138,7 +160,7 @@ impl Display for Choice {
         match self {
             Choice::Default(s) => write!(f, "{}", s),
             Choice::Branch(branch_info) => {
-                write!(f, "{}", branch_info.shorthand,)
+                write!(f, "{}", branch_info.label)
             }
         }
     }
@@ -150,6 +172,144 @@ impl From<BranchInfo> for Choice {
     }
 }
 
+impl PartialEq<&str> for BranchInfo {
+    fn eq(&self, other: &&str) -> bool {
+        self.shorthand == *other
+    }
+}
+
+impl PartialEq<String> for BranchInfo {
+    fn eq(&self, other: &String) -> bool {
+        &self.shorthand == other
+    }
+}
+
+impl PartialEq<&str> for Choice {
+    fn eq(&self, other: &&str) -> bool {
+        match self {
+            Choice::Default(s) => s == other,
+            Choice::Branch(branch_info) => branch_info == other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Checkout,
+    Delete,
+    Rename,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Checkout => write!(f, "Checkout"),
+            Action::Delete => write!(f, "Delete"),
+            Action::Rename => write!(f, "Rename"),
+        }
+    }
+}
+
+/// Prompts for checkout/delete/rename on a selected branch, gated behind `select-branch.actions`.
+fn perform_action(
+    config: &Config,
+    repo: Repository,
+    branch_info: &BranchInfo,
+    current_branch: Option<&str>,
+) -> Result<()> {
+    let actions = available_actions(branch_info.branch_type);
+
+    let selection = Select::with_theme(config.theme.as_ref())
+        .items(actions)
+        .default(0)
+        .with_prompt(format!(
+            "What would you like to do with {}?",
+            branch_info.shorthand
+        ))
+        .interact_opt()
+        .with_context(|| "Prompt interrupted")?;
+
+    match selection.map(|i| actions[i]) {
+        Some(Action::Checkout) => checkout(repo, branch_info),
+        Some(Action::Delete) => delete_branch(config, &repo, branch_info, current_branch),
+        Some(Action::Rename) => rename_branch(config, &repo, branch_info),
+        None => Err(SelectBranchError::Aborted.into()),
+    }
+}
+
+/// Delete/rename only mutate local refs; on a remote branch they'd just touch our
+/// `refs/remotes/*` bookkeeping and reappear on the next fetch, so remote branches only
+/// offer checkout.
+fn available_actions(branch_type: BranchType) -> &'static [Action] {
+    match branch_type {
+        BranchType::Local => &[Action::Checkout, Action::Delete, Action::Rename],
+        BranchType::Remote => &[Action::Checkout],
+    }
+}
+
+fn delete_branch(
+    config: &Config,
+    repo: &Repository,
+    branch_info: &BranchInfo,
+    current_branch: Option<&str>,
+) -> Result<()> {
+    if branch_info.is_protected {
+        return Err(anyhow!(
+            "Refusing to delete \"{}\": it's a protected branch",
+            branch_info.shorthand
+        ));
+    }
+
+    if current_branch == Some(branch_info.shorthand.as_str()) {
+        return Err(anyhow!(
+            "Refusing to delete \"{}\": it's the current branch",
+            branch_info.shorthand
+        ));
+    }
+
+    let confirmed = Confirm::with_theme(config.theme.as_ref())
+        .with_prompt(format!("Delete branch \"{}\"?", branch_info.shorthand))
+        .default(false)
+        .interact_opt()
+        .with_context(|| "Prompt interrupted")?;
+
+    if confirmed != Some(true) {
+        return Err(SelectBranchError::Aborted.into());
+    }
+
+    do_delete_branch(repo, branch_info)
+}
+
+fn do_delete_branch(repo: &Repository, branch_info: &BranchInfo) -> Result<()> {
+    repo.find_branch(&branch_info.shorthand, branch_info.branch_type)?
+        .delete()?;
+
+    Ok(())
+}
+
+fn rename_branch(config: &Config, repo: &Repository, branch_info: &BranchInfo) -> Result<()> {
+    if branch_info.is_protected {
+        return Err(anyhow!(
+            "Refusing to rename \"{}\": it's a protected branch",
+            branch_info.shorthand
+        ));
+    }
+
+    let new_name: String = Input::with_theme(config.theme.as_ref())
+        .with_prompt(format!("New name for \"{}\"", branch_info.shorthand))
+        .interact_text()
+        .with_context(|| "Prompt interrupted")?;
+
+    do_rename_branch(repo, branch_info, &new_name)
+}
+
+fn do_rename_branch(repo: &Repository, branch_info: &BranchInfo, new_name: &str) -> Result<()> {
+    repo.find_branch(&branch_info.shorthand, branch_info.branch_type)?
+        .rename(new_name, false)?;
+
+    Ok(())
+}
+
 fn checkout(repo: Repository, branch_info: &BranchInfo) -> Result<()> {
     let shorthand = branch_info.shorthand.as_str();
     let ref_name = match branch_info.branch_type {
@@ -169,6 +329,7 @@ fn checkout(repo: Repository, branch_info: &BranchInfo) -> Result<()> {
 fn get_branch_options(
     sorted_branches: Vec<BranchInfo>,
     current_branch: Option<&str>,
+    show_detached: bool,
 ) -> Vec<Choice> {
     let mut branches = sorted_branches;
     if let Some(branch) = current_branch {
@@ -181,9 +342,10 @@ fn get_branch_options(
 
     let mut options = Vec::new();
 
-    options.push(Choice::Default(match current_branch {
-        Some(branch) => branch.to_string(),
-        None => "<no branch>".to_string(),
+    options.push(Choice::Default(match (show_detached, current_branch) {
+        (true, _) => "<detached HEAD>".to_string(),
+        (false, Some(branch)) => branch.to_string(),
+        (false, None) => "<no branch>".to_string(),
     }));
 
     options.extend(branches.iter().map(|b| Choice::Branch(b.clone())));
@@ -199,32 +361,64 @@ fn get_current_branch(repo: &Repository) -> Result<Option<String>> {
         .map(|s| s.to_string()))
 }
 
+/// Computes `(ahead, behind)` commit counts for `branch` against its upstream. Returns `None`
+/// when the branch has no upstream configured.
+fn branch_ahead_behind(repo: &Repository, branch: &Branch) -> Result<Option<(usize, usize)>> {
+    let upstream = match branch.upstream() {
+        Ok(upstream) => upstream,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let local_oid = branch.get().peel_to_commit()?.id();
+    let upstream_oid = upstream.get().peel_to_commit()?.id();
+
+    Ok(Some(repo.graph_ahead_behind(local_oid, upstream_oid)?))
+}
+
 fn get_choices(config: &Config, repo: &Repository) -> Result<Vec<BranchInfo>> {
-    Ok(repo
-        .branches(match config.show_remote_branches {
-            true => None,
-            false => Some(BranchType::Local),
-        })?
-        .filter_map(|r| match r {
-            Ok((branch, branch_type)) => {
-                let reference = branch.into_reference();
-                match reference.shorthand() {
-                    Some(shorthand) => match reference.peel_to_commit() {
-                        Ok(commit) => Some(BranchInfo {
-                            shorthand: shorthand.to_string(),
-                            branch_type: branch_type.clone(),
-                            commit_message: commit.message().map(|s| s.to_string()),
-                            commit_author_name: commit.author().name().map(ToString::to_string),
-                            commit_time: commit.time(),
-                        }),
-                        Err(_) => None,
-                    },
-                    None => None,
-                }
-            }
-            Err(_) => None,
-        })
-        .collect())
+    let mut branch_infos = Vec::new();
+
+    for result in repo.branches(match config.show_remote_branches {
+        true => None,
+        false => Some(BranchType::Local),
+    })? {
+        let (branch, branch_type) = match result {
+            Ok(pair) => pair,
+            Err(_) => continue,
+        };
+
+        let shorthand = match branch.get().shorthand().map(ToString::to_string) {
+            Some(shorthand) if config.ignore_branches.iter().any(|b| b == &shorthand) => continue,
+            Some(shorthand) => shorthand,
+            None => continue,
+        };
+
+        let ahead_behind = match branch_type {
+            BranchType::Local => branch_ahead_behind(repo, &branch)?,
+            BranchType::Remote => None,
+        };
+
+        let commit = match branch.into_reference().peel_to_commit() {
+            Ok(commit) => commit,
+            Err(_) => continue,
+        };
+
+        let mut branch_info = BranchInfo {
+            shorthand: shorthand.clone(),
+            branch_type,
+            commit_message: commit.summary().map(|s| s.to_string()),
+            commit_author_name: commit.author().name().map(ToString::to_string),
+            commit_time: commit.time(),
+            label: String::new(),
+            is_protected: config.protected_branches.iter().any(|b| b == &shorthand),
+            ahead_behind,
+        };
+        branch_info.label = format::format_branch_row(config, &branch_info);
+        branch_infos.push(branch_info);
+    }
+
+    Ok(branch_infos)
 }
 
 fn get_sorted_choices(config: &Config, repo: &Repository) -> Result<Vec<BranchInfo>> {
@@ -232,10 +426,13 @@ fn get_sorted_choices(config: &Config, repo: &Repository) -> Result<Vec<BranchIn
 
     choices.sort_by_key(|choice| Reverse(choice.commit_time));
 
-    let branches = match config.limit {
-        Some(limit) => choices.iter().take(limit).map(|c| c.clone()).collect(),
-        None => choices,
-    };
+    let (protected, rest): (Vec<_>, Vec<_>) = choices.into_iter().partition(|c| c.is_protected);
+
+    let mut branches = protected;
+    branches.extend(match config.limit {
+        Some(limit) => rest.into_iter().take(limit).collect(),
+        None => rest,
+    });
     Ok(branches)
 }
 
@@ -243,22 +440,26 @@ fn get_sorted_choices(config: &Config, repo: &Repository) -> Result<Vec<BranchIn
 #[macro_use]
 mod test;
 mod config;
+mod format;
 
 #[cfg(test)]
 mod tests {
     use crate::config::Config;
-    use crate::test::RepoFixture;
-    use crate::{get_branch_options, get_sorted_choices};
+    use crate::test::{FakeEnv, RepoFixture};
+    use crate::{
+        available_actions, delete_branch, do_delete_branch, do_rename_branch, get_branch_options,
+        get_choices, get_sorted_choices, rename_branch, Action, BranchInfo,
+    };
 
     #[test]
     fn test_get_sorted_branches_default_config() {
         let fixture = RepoFixture::new();
-        fixture.create_branch("main", 10).unwrap();
+        fixture.create_branch("first", 10).unwrap();
         fixture.create_branch("second", 20).unwrap();
         fixture.create_branch("third", 30).unwrap();
 
-        let sorted_branches = get_sorted_choices(&Default::default(), &fixture.repo);
-        assert_eq!(sorted_branches.unwrap(), vec!["third", "second", "main"]);
+        let sorted_branches = get_sorted_choices(&Default::default(), fixture.repo());
+        assert_eq!(sorted_branches.unwrap(), vec!["third", "second", "first"]);
     }
 
     #[test]
@@ -272,7 +473,7 @@ mod tests {
             show_remote_branches: true,
             ..Default::default()
         };
-        let sorted_branches = get_sorted_choices(&config, &fixture.repo);
+        let sorted_branches = get_sorted_choices(&config, fixture.repo());
         assert_eq!(sorted_branches.unwrap(), vec!["origin/d", "b", "a", "c"])
     }
 
@@ -286,10 +487,26 @@ mod tests {
             limit: Some(2),
             ..Default::default()
         };
-        let sorted_branches = get_sorted_choices(&config, &fixture.repo).unwrap();
+        let sorted_branches = get_sorted_choices(&config, fixture.repo()).unwrap();
         assert_eq!(sorted_branches, vec!["c", "b"])
     }
 
+    #[test]
+    fn test_get_sorted_branches_protected_branch_survives_limit() {
+        let fixture = RepoFixture::new();
+        fixture.create_branch("old-protected", 1).unwrap();
+        fixture.create_branch("b", 10).unwrap();
+        fixture.create_branch("c", 20).unwrap();
+        fixture.create_branch("d", 30).unwrap();
+        let config = Config {
+            protected_branches: vec!["old-protected".to_string()],
+            limit: Some(2),
+            ..Default::default()
+        };
+        let sorted_branches = get_sorted_choices(&config, fixture.repo()).unwrap();
+        assert_eq!(sorted_branches, vec!["old-protected", "d", "c"])
+    }
+
     #[test]
     fn test_get_sorted_branches_unlimited() {
         let fixture = RepoFixture::new();
@@ -303,14 +520,265 @@ mod tests {
             limit: None,
             ..Default::default()
         };
-        let sorted_branches = get_sorted_choices(&config, &fixture.repo).unwrap();
+        let sorted_branches = get_sorted_choices(&config, fixture.repo()).unwrap();
         assert_eq!(sorted_branches.len(), 100);
         assert_eq!(sorted_branches, expected_sorted_branches)
     }
 
     #[test]
     fn test_get_branch_options() {
-        let options = get_branch_options(vec!["a", "b", "c"], Some("c"));
+        let branches = vec![
+            branch_info("a", false),
+            branch_info("b", false),
+            branch_info("c", false),
+        ];
+        let options = get_branch_options(branches, Some("c"), false);
         assert_eq!(options, vec!["c", "a", "b"])
     }
+
+    #[test]
+    fn test_get_branch_options_detached() {
+        let branches = vec![branch_info("a", false), branch_info("b", false)];
+        let options = get_branch_options(branches, None, true);
+        assert_eq!(options, vec!["<detached HEAD>", "a", "b"])
+    }
+
+    fn branch_info(shorthand: &str, is_protected: bool) -> BranchInfo {
+        BranchInfo {
+            shorthand: shorthand.to_string(),
+            branch_type: git2::BranchType::Local,
+            commit_time: git2::Time::new(0, 0),
+            commit_message: None,
+            commit_author_name: None,
+            label: String::new(),
+            is_protected,
+            ahead_behind: None,
+        }
+    }
+
+    #[test]
+    fn test_config_from_git_config_reads_select_branch_keys() {
+        let fixture = RepoFixture::new();
+        {
+            let mut git_config = fixture.repo().config().unwrap();
+            git_config
+                .set_str("select-branch.format", "$branch")
+                .unwrap();
+            git_config
+                .set_i64("select-branch.truncation-length", 5)
+                .unwrap();
+            git_config
+                .set_str("select-branch.truncation-symbol", "~")
+                .unwrap();
+            git_config
+                .set_str("select-branch.protected-branches", "release, hotfix")
+                .unwrap();
+            git_config
+                .set_str("select-branch.ignore-branches", "gh-pages")
+                .unwrap();
+        }
+
+        let config = fixture.config().unwrap();
+
+        assert_eq!(config.format, "$branch");
+        assert_eq!(config.truncation_length, Some(5));
+        assert_eq!(config.truncation_symbol, "~");
+        assert_eq!(config.protected_branches, vec!["release", "hotfix"]);
+        assert_eq!(config.ignore_branches, vec!["gh-pages"]);
+    }
+
+    #[test]
+    fn test_config_resolve_env_overrides_git_config() {
+        let fixture = RepoFixture::new();
+        fixture
+            .repo()
+            .config()
+            .unwrap()
+            .set_str("select-branch.limit", "5")
+            .unwrap();
+
+        let env = FakeEnv::new().set("GIT_SELECT_BRANCH_LIMIT", "10");
+        let config = fixture.config_with_env(&env).unwrap();
+
+        assert_eq!(config.limit, Some(10));
+    }
+
+    #[test]
+    fn test_available_actions_excludes_delete_and_rename_for_remote_branches() {
+        assert_eq!(
+            available_actions(git2::BranchType::Local),
+            [Action::Checkout, Action::Delete, Action::Rename]
+        );
+        assert_eq!(
+            available_actions(git2::BranchType::Remote),
+            [Action::Checkout]
+        );
+    }
+
+    #[test]
+    fn test_delete_branch_refuses_protected_branch() {
+        let fixture = RepoFixture::new();
+        fixture.create_branch("main", 10).unwrap();
+        fixture.create_branch("feature", 20).unwrap();
+        let config = fixture.config().unwrap();
+
+        let result = delete_branch(
+            &config,
+            fixture.repo(),
+            &branch_info("main", true),
+            Some("feature"),
+        );
+
+        assert!(result.is_err());
+        assert!(fixture.branch_exists("main"));
+    }
+
+    #[test]
+    fn test_delete_branch_refuses_current_branch() {
+        let fixture = RepoFixture::new();
+        fixture.create_branch("main", 10).unwrap();
+        fixture.create_branch("feature", 20).unwrap();
+        let config = fixture.config().unwrap();
+
+        let result = delete_branch(
+            &config,
+            fixture.repo(),
+            &branch_info("feature", false),
+            Some("feature"),
+        );
+
+        assert!(result.is_err());
+        assert!(fixture.branch_exists("feature"));
+    }
+
+    #[test]
+    fn test_do_delete_branch_deletes_the_branch() {
+        let fixture = RepoFixture::new();
+        fixture.create_branch("feature", 20).unwrap();
+
+        do_delete_branch(fixture.repo(), &branch_info("feature", false)).unwrap();
+
+        assert!(!fixture.branch_exists("feature"));
+    }
+
+    #[test]
+    fn test_rename_branch_refuses_protected_branch() {
+        let fixture = RepoFixture::new();
+        fixture.create_branch("main", 10).unwrap();
+        let config = fixture.config().unwrap();
+
+        let result = rename_branch(&config, fixture.repo(), &branch_info("main", true));
+
+        assert!(result.is_err());
+        assert!(fixture.branch_exists("main"));
+    }
+
+    #[test]
+    fn test_do_rename_branch_renames_the_branch() {
+        let fixture = RepoFixture::new();
+        fixture.create_branch("feature", 20).unwrap();
+
+        do_rename_branch(fixture.repo(), &branch_info("feature", false), "renamed").unwrap();
+
+        assert!(!fixture.branch_exists("feature"));
+        assert!(fixture.branch_exists("renamed"));
+    }
+
+    #[test]
+    fn test_get_choices_ahead_behind() {
+        let fixture = RepoFixture::new();
+        fixture.create_branch("main", 10).unwrap();
+        fixture.create_branch("feature", 20).unwrap();
+        fixture
+            .branch_at("refs/remotes/origin/feature", "refs/heads/feature")
+            .unwrap();
+
+        for time in [21, 22] {
+            fixture.commit_on("refs/heads/feature", time).unwrap();
+        }
+        for time in [21, 22, 23] {
+            fixture
+                .commit_on("refs/remotes/origin/feature", time)
+                .unwrap();
+        }
+        fixture.set_upstream("feature", "origin/feature").unwrap();
+
+        let choices = get_choices(&fixture.config().unwrap(), fixture.repo()).unwrap();
+
+        let feature = choices.iter().find(|b| b.shorthand == "feature").unwrap();
+        assert_eq!(feature.ahead_behind, Some((2, 3)));
+
+        let main = choices.iter().find(|b| b.shorthand == "main").unwrap();
+        assert_eq!(main.ahead_behind, None);
+    }
+
+    #[test]
+    fn test_get_choices_ignore_branches_filters_them_out() {
+        let fixture = RepoFixture::new();
+        fixture.create_branch("main", 10).unwrap();
+        fixture.create_branch("gh-pages", 20).unwrap();
+        let config = Config {
+            ignore_branches: vec!["gh-pages".to_string()],
+            ..Default::default()
+        };
+
+        let choices = get_choices(&config, fixture.repo()).unwrap();
+
+        assert!(choices.iter().all(|b| b.shorthand != "gh-pages"));
+        assert!(choices.iter().any(|b| b.shorthand == "main"));
+    }
+
+    #[test]
+    fn test_format_branch_row_truncates_long_branch_names() {
+        let config = Config {
+            format: "$branch".to_string(),
+            truncation_length: Some(5),
+            truncation_symbol: "~".to_string(),
+            ..Default::default()
+        };
+        let row = crate::format::format_branch_row(&config, &branch_info("feature-branch", false));
+        assert_eq!(row, "featu~");
+    }
+
+    #[test]
+    fn test_format_branch_row_does_not_truncate_short_branch_names() {
+        let config = Config {
+            format: "$branch".to_string(),
+            truncation_length: Some(5),
+            truncation_symbol: "~".to_string(),
+            ..Default::default()
+        };
+        let row = crate::format::format_branch_row(&config, &branch_info("abc", false));
+        assert_eq!(row, "abc");
+    }
+
+    #[test]
+    fn test_format_branch_row_token_requires_word_boundary() {
+        let config = Config {
+            format: "$authored_by".to_string(),
+            ..Default::default()
+        };
+        let mut info = branch_info("feature", false);
+        info.commit_author_name = Some("Ada".to_string());
+
+        let row = crate::format::format_branch_row(&config, &info);
+
+        assert_eq!(row, "$authored_by");
+    }
+
+    #[test]
+    fn test_format_branch_row_protected_symbol_is_configurable() {
+        let config = Config {
+            format: "$protected$branch".to_string(),
+            protected_symbol: "[P] ".to_string(),
+            ..Default::default()
+        };
+
+        let protected_row = crate::format::format_branch_row(&config, &branch_info("main", true));
+        assert_eq!(protected_row, "[P] main");
+
+        let unprotected_row =
+            crate::format::format_branch_row(&config, &branch_info("feature", false));
+        assert_eq!(unprotected_row, "feature");
+    }
 }
@@ -1,9 +1,33 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
-use git2::{Repository, RepositoryInitOptions, Signature, Time};
+use git2::{BranchType, Repository, RepositoryInitOptions, Signature, Time};
 
-use crate::config::Config;
+use crate::config::{Config, EnvSource};
 use tempfile::TempDir;
 
+/// A fake `EnvSource` for exercising `Config::resolve`'s environment-variable layer without
+/// touching the real process environment.
+#[derive(Default)]
+pub struct FakeEnv(HashMap<String, String>);
+
+impl FakeEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, key: &str, value: &str) -> Self {
+        self.0.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+impl EnvSource for FakeEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+}
+
 pub fn repo_init() -> (TempDir, Repository) {
     let td = TempDir::new().unwrap();
     let mut opts = RepositoryInitOptions::new();
@@ -41,6 +65,14 @@ impl RepoFixture {
         Ok(Config::from_git_config(&self.repo.config()?.snapshot()?)?)
     }
 
+    pub fn config_with_env(&self, env: &FakeEnv) -> Result<Config> {
+        Ok(Config::resolve(&self.repo.config()?.snapshot()?, env)?)
+    }
+
+    pub fn branch_exists(&self, name: &str) -> bool {
+        self.repo.find_branch(name, BranchType::Local).is_ok()
+    }
+
     pub fn create_branch(&self, name: &str, commit_time_seconds: i64) -> Result<()> {
         let time = Time::new(commit_time_seconds, 0);
         let default_signature = self.repo.signature()?;
@@ -89,4 +121,56 @@ impl RepoFixture {
         )?;
         Ok(())
     }
+
+    pub fn set_upstream(&self, branch_name: &str, upstream_name: &str) -> Result<()> {
+        if let Some((remote_name, _)) = upstream_name.split_once('/') {
+            if self.repo.find_remote(remote_name).is_err() {
+                self.repo
+                    .remote(remote_name, "https://example.com/repo.git")?;
+            }
+        }
+
+        self.repo
+            .find_branch(branch_name, BranchType::Local)?
+            .set_upstream(Some(upstream_name))?;
+        Ok(())
+    }
+
+    /// Points `ref_name` at the same commit as `source_ref_name`, without creating a new commit.
+    /// Used to give two refs a shared base to diverge from, e.g. a local branch and its upstream.
+    pub fn branch_at(&self, ref_name: &str, source_ref_name: &str) -> Result<()> {
+        let oid = self
+            .repo
+            .find_reference(source_ref_name)?
+            .peel_to_commit()?
+            .id();
+        self.repo.reference(ref_name, oid, false, "branch_at")?;
+        Ok(())
+    }
+
+    /// Adds a new commit as a child of `ref_name`'s current tip, advancing it forward. Unlike
+    /// `create_branch`/`create_remote_branch`, which always start an orphan root, this lets tests
+    /// build real, divergent commit histories.
+    pub fn commit_on(&self, ref_name: &str, commit_time_seconds: i64) -> Result<()> {
+        let time = Time::new(commit_time_seconds, 0);
+        let default_signature = self.repo.signature()?;
+        let signature = Signature::new(
+            default_signature.name().unwrap(),
+            default_signature.email().unwrap(),
+            &time,
+        )?;
+        let tree_id = self.repo.index()?.write_tree()?;
+        let tree = self.repo.find_tree(tree_id)?;
+        let parent = self.repo.find_reference(ref_name)?.peel_to_commit()?;
+
+        self.repo.commit(
+            Some(ref_name),
+            &signature,
+            &signature,
+            format!("commit on {ref_name} at {:?}", time).as_str(),
+            &tree,
+            &[&parent],
+        )?;
+        Ok(())
+    }
 }